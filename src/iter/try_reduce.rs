@@ -1,29 +1,60 @@
 use super::plumbing::*;
-use super::ParallelIterator;
+use super::{IndexedParallelIterator, ParallelIterator};
 
 use super::private::Try;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::usize;
 
+// Picking the earliest error by position (see `TryReduceConsumer` below)
+// only makes sense when the source has a real, stable iteration order that
+// `split_at` can report positions against; that's why this is bound to
+// `IndexedParallelIterator` rather than the more permissive
+// `ParallelIterator` (unlike `try_reduce_with`, which has no such need).
 pub fn try_reduce<PI, R, ID, T>(pi: PI, identity: ID, reduce_op: R) -> T
 where
-    PI: ParallelIterator<Item = T>,
+    PI: IndexedParallelIterator<Item = T>,
     R: Fn(T::Ok, T::Ok) -> T + Sync,
     ID: Fn() -> T::Ok + Sync,
     T: Try + Send,
 {
-    let full = AtomicBool::new(false);
+    let best_error_index = AtomicUsize::new(usize::MAX);
     let consumer = TryReduceConsumer {
         identity: &identity,
         reduce_op: &reduce_op,
-        full: &full,
+        index: 0,
+        best_error_index: &best_error_index,
     };
-    pi.drive_unindexed(consumer)
+    pi.drive(consumer).result
+}
+
+// A `T` along with the position (in the original iteration order) at which
+// its error, if any, was produced. `usize::MAX` marks an `Ok` result, so
+// "no error" always sorts after a real one when comparing indices.
+struct Indexed<T> {
+    result: T,
+    error_index: usize,
+}
+
+// Atomically lowers `atomic` to `value`, leaving it unchanged if it is
+// already `<= value` (a stable-friendly stand-in for `AtomicUsize::fetch_min`).
+fn fetch_min(atomic: &AtomicUsize, value: usize) {
+    let mut current = atomic.load(Ordering::Relaxed);
+    while value < current {
+        match atomic.compare_exchange_weak(current, value, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => break,
+            Err(found) => current = found,
+        }
+    }
 }
 
 struct TryReduceConsumer<'r, R: 'r, ID: 'r> {
     identity: &'r ID,
     reduce_op: &'r R,
-    full: &'r AtomicBool,
+    // the index, in the original iteration order, of the first item this
+    // consumer is responsible for
+    index: usize,
+    best_error_index: &'r AtomicUsize,
 }
 
 impl<'r, R, ID> Copy for TryReduceConsumer<'r, R, ID> {}
@@ -42,16 +73,105 @@ where
 {
     type Folder = TryReduceFolder<'r, R, T>;
     type Reducer = Self;
-    type Result = T;
+    type Result = Indexed<T>;
 
-    fn split_at(self, _index: usize) -> (Self, Self, Self) {
-        (self, self, self)
+    fn split_at(self, index: usize) -> (Self, Self, Self) {
+        let right = TryReduceConsumer {
+            index: self.index + index,
+            ..self
+        };
+        (self, right, self)
     }
 
     fn into_folder(self) -> Self::Folder {
         TryReduceFolder {
             reduce_op: self.reduce_op,
             result: Ok((self.identity)()),
+            index: self.index,
+            error_index: usize::MAX,
+            best_error_index: self.best_error_index,
+        }
+    }
+
+    fn full(&self) -> bool {
+        self.best_error_index.load(Ordering::Relaxed) < self.index
+    }
+}
+
+// Deliberately no `UnindexedConsumer` impl: the earliest-error bookkeeping
+// above depends on `split_at` reporting real positions, which only happens
+// on the indexed path (`bridge()`), not on `bridge_unindexed()`'s
+// `split_off_left`. See the comment on `try_reduce`.
+
+impl<'r, R, ID, T> Reducer<Indexed<T>> for TryReduceConsumer<'r, R, ID>
+where
+    R: Fn(T::Ok, T::Ok) -> T + Sync,
+    T: Try,
+{
+    fn reduce(self, left: Indexed<T>, right: Indexed<T>) -> Indexed<T> {
+        // `left` always precedes `right` in iteration order.
+        if left.error_index > right.error_index {
+            return right;
+        }
+        if left.error_index != usize::MAX {
+            // `left` carries the earliest known error; `right` is discarded.
+            return left;
+        }
+        // A tie only happens when neither side has errored.
+        match (left.result.into_result(), right.result.into_result()) {
+            (Ok(left), Ok(right)) => Indexed {
+                result: (self.reduce_op)(left, right),
+                error_index: usize::MAX,
+            },
+            _ => unreachable!("error_index should track whether `result` is `Err`"),
+        }
+    }
+}
+
+pub fn try_reduce_with<PI, R, T>(pi: PI, reduce_op: R) -> Option<T>
+where
+    PI: ParallelIterator<Item = T>,
+    R: Fn(T::Ok, T::Ok) -> T + Sync,
+    T: Try + Send,
+{
+    let full = AtomicBool::new(false);
+    let consumer = TryReduceWithConsumer {
+        reduce_op: &reduce_op,
+        full: &full,
+    };
+    pi.drive_unindexed(consumer)
+}
+
+struct TryReduceWithConsumer<'r, R: 'r> {
+    reduce_op: &'r R,
+    full: &'r AtomicBool,
+}
+
+impl<'r, R> Copy for TryReduceWithConsumer<'r, R> {}
+
+impl<'r, R> Clone for TryReduceWithConsumer<'r, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'r, R, T> Consumer<T> for TryReduceWithConsumer<'r, R>
+where
+    R: Fn(T::Ok, T::Ok) -> T + Sync,
+    T: Try + Send,
+{
+    type Folder = TryReduceWithFolder<'r, R, T>;
+    type Reducer = Self;
+    type Result = Option<T>;
+
+    fn split_at(self, _index: usize) -> (Self, Self, Self) {
+        (self, self, self)
+    }
+
+    fn into_folder(self) -> Self::Folder {
+        TryReduceWithFolder {
+            reduce_op: self.reduce_op,
+            result: None,
             full: self.full,
         }
     }
@@ -61,10 +181,9 @@ where
     }
 }
 
-impl<'r, R, ID, T> UnindexedConsumer<T> for TryReduceConsumer<'r, R, ID>
+impl<'r, R, T> UnindexedConsumer<T> for TryReduceWithConsumer<'r, R>
 where
     R: Fn(T::Ok, T::Ok) -> T + Sync,
-    ID: Fn() -> T::Ok + Sync,
     T: Try + Send,
 {
     fn split_off_left(&self) -> Self {
@@ -76,23 +195,128 @@ where
     }
 }
 
-impl<'r, R, ID, T> Reducer<T> for TryReduceConsumer<'r, R, ID>
+impl<'r, R, T> Reducer<Option<T>> for TryReduceWithConsumer<'r, R>
 where
     R: Fn(T::Ok, T::Ok) -> T + Sync,
     T: Try,
 {
-    fn reduce(self, left: T, right: T) -> T {
-        match (left.into_result(), right.into_result()) {
-            (Ok(left), Ok(right)) => (self.reduce_op)(left, right),
-            (Err(e), _) | (_, Err(e)) => T::from_error(e),
+    fn reduce(self, left: Option<T>, right: Option<T>) -> Option<T> {
+        match (left, right) {
+            (None, right) => right,
+            (left, None) => left,
+            (Some(left), Some(right)) => match (left.into_result(), right.into_result()) {
+                (Ok(left), Ok(right)) => Some((self.reduce_op)(left, right)),
+                (Err(e), _) | (_, Err(e)) => Some(T::from_error(e)),
+            },
         }
     }
 }
 
+struct TryReduceWithFolder<'r, R: 'r, T: Try> {
+    reduce_op: &'r R,
+    result: Option<Result<T::Ok, T::Error>>,
+    full: &'r AtomicBool,
+}
+
+impl<'r, R, T> Folder<T> for TryReduceWithFolder<'r, R, T>
+where
+    R: Fn(T::Ok, T::Ok) -> T,
+    T: Try,
+{
+    type Result = Option<T>;
+
+    fn consume(self, item: T) -> Self {
+        let reduce_op = self.reduce_op;
+        let result = match self.result {
+            None => Some(item.into_result()),
+            Some(left) => Some(left.and_then(|left| reduce_op(left, item.into_result()?).into_result())),
+        };
+        if let Some(Err(_)) = result {
+            self.full.store(true, Ordering::Relaxed)
+        }
+        TryReduceWithFolder {
+            result: result,
+            ..self
+        }
+    }
+
+    #[cfg(has_try_fold)]
+    fn consume_iter<I>(self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let full = self.full;
+        let reduce_op = self.reduce_op;
+        let mut iter = iter.into_iter();
+
+        // Adopt the first item as the seed if we don't have one yet.
+        let mut acc = match self.result {
+            Some(result) => result,
+            None => match iter.next() {
+                Some(item) => item.into_result(),
+                None => {
+                    return TryReduceWithFolder {
+                        result: None,
+                        ..self
+                    };
+                }
+            },
+        };
+
+        acc = acc.and_then(|left| {
+            let inner_result = iter.try_fold(left, |acc, item| {
+                let this_step = item
+                    .into_result()
+                    .and_then(|right| reduce_op(acc, right).into_result());
+
+                match this_step {
+                    // break
+                    Err(_) => Err(this_step),
+                    _ if full.load(Ordering::Relaxed) => Err(this_step),
+                    // continue
+                    Ok(value) => Ok(value),
+                }
+            });
+
+            match inner_result {
+                Err(result) => result,
+                Ok(value) => Ok(value),
+            }
+        });
+
+        if acc.is_err() {
+            full.store(true, Ordering::Relaxed);
+        }
+
+        TryReduceWithFolder {
+            result: Some(acc),
+            ..self
+        }
+    }
+
+    fn complete(self) -> Option<T> {
+        match self.result {
+            None => None,
+            Some(Ok(ok)) => Some(T::from_ok(ok)),
+            Some(Err(error)) => Some(T::from_error(error)),
+        }
+    }
+
+    fn full(&self) -> bool {
+        self.full.load(Ordering::Relaxed)
+    }
+}
+
 struct TryReduceFolder<'r, R: 'r, T: Try> {
     reduce_op: &'r R,
     result: Result<T::Ok, T::Error>,
-    full: &'r AtomicBool,
+    // the index, in the original iteration order, of the next item this
+    // folder will consume
+    index: usize,
+    // the index at which `result` became `Err`, or `usize::MAX` if it is
+    // still `Ok`
+    error_index: usize,
+    best_error_index: &'r AtomicUsize,
 }
 
 impl<'r, R, T> Folder<T> for TryReduceFolder<'r, R, T>
@@ -100,18 +324,24 @@ where
     R: Fn(T::Ok, T::Ok) -> T,
     T: Try,
 {
-    type Result = T;
+    type Result = Indexed<T>;
 
     fn consume(self, item: T) -> Self {
+        let index = self.index;
         let reduce_op = self.reduce_op;
         let result = self
             .result
             .and_then(|left| reduce_op(left, item.into_result()?).into_result());
-        if result.is_err() {
-            self.full.store(true, Ordering::Relaxed)
-        }
+        let error_index = if result.is_err() && self.error_index == usize::MAX {
+            fetch_min(self.best_error_index, index);
+            index
+        } else {
+            self.error_index
+        };
         TryReduceFolder {
             result: result,
+            index: index + 1,
+            error_index: error_index,
             ..self
         }
     }
@@ -121,22 +351,31 @@ where
     where
         I: IntoIterator<Item = T>
     {
-        let full = self.full;
+        let best_error_index = self.best_error_index;
         let reduce_op = self.reduce_op;
+        let mut index = self.index;
+        let mut error_index = self.error_index;
         let result = self
             .result
             .and_then(|left| {
                 let inner_result = iter.into_iter().try_fold(left, |acc, item| {
+                    let this_index = index;
+                    index += 1;
                     let this_step = item
                         .into_result()
                         .and_then(|right| {
                             reduce_op(acc, right).into_result()
                         });
 
+                    if this_step.is_err() && error_index == usize::MAX {
+                        fetch_min(best_error_index, this_index);
+                        error_index = this_index;
+                    }
+
                     match this_step {
                         // break
                         Err(_) => Err(this_step),
-                        _ if full.load(Ordering::Relaxed) => Err(this_step),
+                        _ if best_error_index.load(Ordering::Relaxed) < index => Err(this_step),
                         // continue
                         Ok(value) => Ok(value),
                     }
@@ -147,23 +386,307 @@ where
                     Ok(value) => Ok(value)
                 }
             });
-        if result.is_err() {
-            self.full.store(true, Ordering::Relaxed);
-        }
         TryReduceFolder {
             result: result,
+            index: index,
+            error_index: error_index,
             ..self
         }
     }
 
-    fn complete(self) -> T {
-        match self.result {
+    fn complete(self) -> Indexed<T> {
+        let result = match self.result {
             Ok(ok) => T::from_ok(ok),
             Err(error) => T::from_error(error),
+        };
+        Indexed {
+            result: result,
+            error_index: self.error_index,
         }
     }
 
     fn full(&self) -> bool {
-        self.full.load(Ordering::Relaxed)
+        self.best_error_index.load(Ordering::Relaxed) < self.index
+    }
+}
+
+// "First" only means something when the source has a real, stable
+// iteration order that `split_at` can report positions against, so this is
+// bound to `IndexedParallelIterator` rather than `ParallelIterator` — see
+// the comment on `try_reduce` for the same reasoning.
+pub fn try_find_first<PI, F, T, R>(pi: PI, predicate: F) -> Result<Option<T>, R::Error>
+where
+    PI: IndexedParallelIterator<Item = T>,
+    F: Fn(&T) -> R + Sync,
+    R: Try<Ok = bool>,
+    R::Error: Send,
+    T: Send,
+{
+    let best_index = AtomicUsize::new(usize::MAX);
+    let consumer = TryFindFirstConsumer {
+        predicate: &predicate,
+        index: 0,
+        best_index: &best_index,
+        marker: PhantomData,
+    };
+    match pi.drive(consumer).outcome {
+        None => Ok(None),
+        Some(Ok(item)) => Ok(Some(item)),
+        Some(Err(error)) => Err(error),
+    }
+}
+
+// The outcome of scanning some region of the iterator: `index` is the
+// position, in the original iteration order, of the first match or error
+// found there, or `usize::MAX` if the region held neither.
+struct Found<T, E> {
+    index: usize,
+    outcome: Option<Result<T, E>>,
+}
+
+struct TryFindFirstConsumer<'r, F: 'r, R> {
+    predicate: &'r F,
+    // the index, in the original iteration order, of the first item this
+    // consumer is responsible for
+    index: usize,
+    best_index: &'r AtomicUsize,
+    // None of the other fields name `R` (only the `Found<T, R::Error>` we
+    // produce does), so this just carries it for the impls below to hang
+    // off of.
+    marker: PhantomData<fn() -> R>,
+}
+
+impl<'r, F, R> Copy for TryFindFirstConsumer<'r, F, R> {}
+
+impl<'r, F, R> Clone for TryFindFirstConsumer<'r, F, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'r, F, T, R> Consumer<T> for TryFindFirstConsumer<'r, F, R>
+where
+    F: Fn(&T) -> R + Sync,
+    R: Try<Ok = bool>,
+    R::Error: Send,
+    T: Send,
+{
+    type Folder = TryFindFirstFolder<'r, F, T, R>;
+    type Reducer = Self;
+    type Result = Found<T, R::Error>;
+
+    fn split_at(self, index: usize) -> (Self, Self, Self) {
+        let right = TryFindFirstConsumer {
+            index: self.index + index,
+            ..self
+        };
+        (self, right, self)
+    }
+
+    fn into_folder(self) -> Self::Folder {
+        TryFindFirstFolder {
+            predicate: self.predicate,
+            index: self.index,
+            found: None,
+            best_index: self.best_index,
+        }
+    }
+
+    fn full(&self) -> bool {
+        self.best_index.load(Ordering::Relaxed) < self.index
+    }
+}
+
+// Deliberately no `UnindexedConsumer` impl: `index`/`best_index` track real
+// positions from `split_at`, which only happens on the indexed path
+// (`bridge()`), not on `bridge_unindexed()`'s `split_off_left`. See
+// the comment on `try_find_first`.
+
+impl<'r, F, T, R> Reducer<Found<T, R::Error>> for TryFindFirstConsumer<'r, F, R>
+where
+    R: Try<Ok = bool>,
+{
+    fn reduce(self, left: Found<T, R::Error>, right: Found<T, R::Error>) -> Found<T, R::Error> {
+        // `left` always precedes `right` in iteration order, so on a tie
+        // (neither side found anything) it doesn't matter which is kept.
+        if left.index <= right.index {
+            left
+        } else {
+            right
+        }
+    }
+}
+
+struct TryFindFirstFolder<'r, F: 'r, T, R: Try<Ok = bool>> {
+    predicate: &'r F,
+    // the index, in the original iteration order, of the next item this
+    // folder will consume
+    index: usize,
+    found: Option<(usize, Result<T, R::Error>)>,
+    best_index: &'r AtomicUsize,
+}
+
+impl<'r, F, T, R> Folder<T> for TryFindFirstFolder<'r, F, T, R>
+where
+    F: Fn(&T) -> R,
+    R: Try<Ok = bool>,
+{
+    type Result = Found<T, R::Error>;
+
+    fn consume(self, item: T) -> Self {
+        let index = self.index;
+        let mut found = self.found;
+        if found.is_none() {
+            found = match (self.predicate)(&item).into_result() {
+                Ok(true) => {
+                    fetch_min(self.best_index, index);
+                    Some((index, Ok(item)))
+                }
+                Ok(false) => None,
+                Err(error) => {
+                    fetch_min(self.best_index, index);
+                    Some((index, Err(error)))
+                }
+            };
+        }
+        TryFindFirstFolder {
+            found: found,
+            index: index + 1,
+            ..self
+        }
+    }
+
+    #[cfg(has_try_fold)]
+    fn consume_iter<I>(self, iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        if self.found.is_some() {
+            return self;
+        }
+        let predicate = self.predicate;
+        let best_index = self.best_index;
+        let mut index = self.index;
+        let step = iter.into_iter().try_fold((), |_, item| {
+            let this_index = index;
+            index += 1;
+            match predicate(&item).into_result() {
+                Ok(true) => {
+                    fetch_min(best_index, this_index);
+                    Err(Some((this_index, Ok(item))))
+                }
+                Ok(false) if best_index.load(Ordering::Relaxed) < index => Err(None),
+                Ok(false) => Ok(()),
+                Err(error) => {
+                    fetch_min(best_index, this_index);
+                    Err(Some((this_index, Err(error))))
+                }
+            }
+        });
+        let found = match step {
+            Ok(()) => None,
+            Err(found) => found,
+        };
+        TryFindFirstFolder {
+            found: found,
+            index: index,
+            ..self
+        }
+    }
+
+    fn complete(self) -> Found<T, R::Error> {
+        match self.found {
+            Some((index, outcome)) => Found {
+                index: index,
+                outcome: Some(outcome),
+            },
+            None => Found {
+                index: usize::MAX,
+                outcome: None,
+            },
+        }
+    }
+
+    fn full(&self) -> bool {
+        self.found.is_some() || self.best_index.load(Ordering::Relaxed) < self.index
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::IntoParallelIterator;
+
+    #[test]
+    fn try_reduce_with_empty() {
+        let v: Vec<Result<i32, ()>> = vec![];
+        let result = try_reduce_with(v.into_par_iter(), |a, b| Ok(a + b));
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn try_reduce_with_sums() {
+        let v: Vec<Result<i32, ()>> = (1..=5).map(Ok).collect();
+        let result = try_reduce_with(v.into_par_iter(), |a, b| Ok(a + b));
+        assert_eq!(result, Some(Ok(15)));
+    }
+
+    #[test]
+    fn try_reduce_with_short_circuits_on_error() {
+        let v: Vec<Result<i32, &'static str>> = vec![Ok(1), Ok(2), Err("boom"), Ok(4)];
+        let result = try_reduce_with(v.into_par_iter(), |a, b| Ok(a + b));
+        assert_eq!(result, Some(Err("boom")));
+    }
+
+    #[test]
+    fn try_reduce_empty_returns_identity() {
+        let v: Vec<Result<i32, ()>> = vec![];
+        let result = try_reduce(v.into_par_iter(), || 0, |a, b| Ok(a + b));
+        assert_eq!(result, Ok(0));
+    }
+
+    #[test]
+    fn try_reduce_picks_earliest_error_deterministically() {
+        // Two errors; the one at the lower index should always win, no
+        // matter how the work happens to get split up across threads.
+        let v: Vec<Result<i32, usize>> = (0..1000)
+            .map(|i| if i == 3 || i == 500 { Err(i) } else { Ok(1) })
+            .collect();
+        for _ in 0..20 {
+            let result = try_reduce(v.clone().into_par_iter(), || 0, |a, b| Ok(a + b));
+            assert_eq!(result, Err(3));
+        }
+    }
+
+    #[test]
+    fn try_find_first_empty() {
+        let v: Vec<i32> = vec![];
+        let result: Result<Option<i32>, ()> = try_find_first(v.into_par_iter(), |&x| Ok(x > 0));
+        assert_eq!(result, Ok(None));
+    }
+
+    #[test]
+    fn try_find_first_finds_earliest_match() {
+        // Several indices match; we should always get back the earliest one.
+        let v: Vec<i32> = (0..1000).collect();
+        for _ in 0..20 {
+            let result: Result<Option<i32>, ()> =
+                try_find_first(v.clone().into_par_iter(), |&x| Ok(x > 500));
+            assert_eq!(result, Ok(Some(501)));
+        }
+    }
+
+    #[test]
+    fn try_find_first_propagates_earliest_error() {
+        // The error at index 10 should win even though a match exists later.
+        let v: Vec<i32> = (0..1000).collect();
+        let result = try_find_first(v.into_par_iter(), |&x| {
+            if x == 10 {
+                Err("boom")
+            } else {
+                Ok(x == 999)
+            }
+        });
+        assert_eq!(result, Err("boom"));
     }
 }